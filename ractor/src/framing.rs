@@ -0,0 +1,384 @@
+// Copyright (c) Sean Lawlor
+//
+// This source code is licensed under both the MIT license found in the
+// LICENSE-MIT file in the root directory of this source tree.
+
+//! Transport-layer framing for `ractor_cluster` messages, sitting directly on top of
+//! [`crate::serialization`]: a [`BytesConvertable::into_bytes`] payload goes through
+//! [`encode_frame`] before it hits the socket, and the raw bytes read back off the socket go
+//! through [`decode_frame`] before being handed to [`BytesConvertable::from_bytes`] /
+//! [`crate::try_decode`].
+//!
+//! Framing is a single header byte followed by the (possibly compressed, possibly encrypted)
+//! payload:
+//!
+//! * bit 0 -- the payload was zlib-deflated because it was larger than the configured
+//!   [`FrameOptions::compression_threshold`], mirroring the compression-threshold scheme used by
+//!   packet-based network protocols: small messages aren't worth the CPU, large ones are.
+//!   Inflating is capped at [`FrameOptions::max_inflated_size`], so a small malicious frame can't
+//!   be used as a zlib decompression bomb to exhaust memory.
+//! * bit 1 -- the payload is AES-128 CFB8 encrypted with a session key negotiated out of band,
+//!   then authenticated encrypt-then-MAC style: a random 16-byte IV is prepended to the
+//!   ciphertext and an HMAC-SHA256 tag -- computed over the header byte itself as well as the IV
+//!   and ciphertext -- is appended, so a network attacker can't flip ciphertext bytes, or the
+//!   header's other flag bits, without `decode_frame` detecting the tampering. Clearing this bit
+//!   specifically can't be used to skip decryption and MAC verification either: whenever
+//!   `decode_frame` is given a session key it requires the bit to be set, and treats a frame that
+//!   claims otherwise as a failed authentication rather than as plaintext. CFB8 alone has no
+//!   integrity protection; the MAC (and that invariant) are what make this usable against an
+//!   active adversary, not just a passive eavesdropper.
+//! * bits 2-7 -- reserved, always written as zero and ignored on read, so future modes can be
+//!   added without breaking readers that only understand today's two bits.
+
+use cfb8::cipher::{AsyncStreamCipher, KeyIvInit};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::io::{Read, Write};
+
+const FLAG_COMPRESSED: u8 = 0b0000_0001;
+const FLAG_ENCRYPTED: u8 = 0b0000_0010;
+
+/// Length in bytes of the HMAC-SHA256 authentication tag appended to an encrypted frame
+const MAC_LEN: usize = 32;
+
+type Aes128Cfb8Enc = cfb8::Encryptor<aes::Aes128>;
+type Aes128Cfb8Dec = cfb8::Decryptor<aes::Aes128>;
+type HmacSha256 = Hmac<Sha256>;
+
+/// Errors which can occur while framing or de-framing a transport payload
+#[derive(Debug, thiserror::Error)]
+pub enum FramingError {
+    /// The frame's compressed section failed to zlib-inflate
+    #[error("failed to inflate compressed frame: {0}")]
+    Decompress(#[from] std::io::Error),
+    /// Inflating the frame's compressed section would have exceeded `max_inflated_size`
+    #[error("decompressed frame exceeds the {max_inflated_size}-byte limit")]
+    DecompressedTooLarge {
+        /// The configured ceiling that was exceeded
+        max_inflated_size: usize,
+    },
+    /// The frame was marked encrypted but no session key was supplied to decode it with
+    #[error("received an encrypted frame but no session key was configured for this connection")]
+    MissingSessionKey,
+    /// The frame was marked encrypted but was too short to contain its IV and MAC tag
+    #[error("encrypted frame is shorter than the {} bytes its IV and MAC tag require", 16 + MAC_LEN)]
+    Truncated,
+    /// The frame's HMAC tag didn't match -- it was corrupted or tampered with in transit
+    #[error("encrypted frame failed authentication -- it was corrupted or tampered with")]
+    AuthenticationFailed,
+}
+
+/// A 16-byte AES-128 session key, negotiated out of band between two cluster peers
+pub type SessionKey = [u8; 16];
+
+/// Controls how [`encode_frame`] and [`decode_frame`] treat a given payload
+#[derive(Debug, Clone, Copy)]
+pub struct FrameOptions<'a> {
+    /// Payloads larger than this many bytes are zlib-deflated before being sent. Smaller
+    /// payloads are sent as-is, since the deflate header overhead isn't worth it
+    pub compression_threshold: usize,
+    /// When set, the payload is AES-128 CFB8 encrypted (and HMAC-SHA256 authenticated) with this
+    /// session key
+    pub session_key: Option<&'a SessionKey>,
+    /// On decode, a compressed frame that would inflate past this many bytes is rejected rather
+    /// than fully decompressed, so a small malicious frame can't be used as a decompression bomb
+    pub max_inflated_size: usize,
+}
+
+impl Default for FrameOptions<'_> {
+    fn default() -> Self {
+        Self {
+            compression_threshold: 256,
+            session_key: None,
+            max_inflated_size: crate::DEFAULT_DECODE_LIMIT,
+        }
+    }
+}
+
+/// Frame a [`crate::BytesConvertable::into_bytes`] payload for the wire: optionally
+/// zlib-compresses it above `options.compression_threshold`, optionally AES-128 CFB8 encrypts it
+/// with `options.session_key`, and prepends a one-byte header recording which of those happened
+pub fn encode_frame(payload: Vec<u8>, options: FrameOptions<'_>) -> Vec<u8> {
+    let mut flags = 0u8;
+
+    let mut body = if payload.len() > options.compression_threshold {
+        flags |= FLAG_COMPRESSED;
+        deflate(&payload)
+    } else {
+        payload
+    };
+
+    if let Some(key) = options.session_key {
+        flags |= FLAG_ENCRYPTED;
+        body = encrypt(key, flags, &body);
+    }
+
+    let mut result = Vec::with_capacity(body.len() + 1);
+    result.push(flags);
+    result.extend(body);
+    result
+}
+
+/// Reverse [`encode_frame`]: strips the header byte, verifies and decrypts with
+/// `options.session_key` if one is configured (requiring, not just trusting, that the header
+/// says the frame is encrypted -- see the module docs), then inflates it (capped at
+/// `options.max_inflated_size`) if the header says it's compressed
+pub fn decode_frame(bytes: Vec<u8>, options: FrameOptions<'_>) -> Result<Vec<u8>, FramingError> {
+    let Some((&flags, rest)) = bytes.split_first() else {
+        // No header at all can't carry FLAG_ENCRYPTED either, so with a session key configured
+        // it fails authentication just like a frame whose bit was cleared
+        if options.session_key.is_some() {
+            return Err(FramingError::AuthenticationFailed);
+        }
+        return Ok(Vec::new());
+    };
+
+    let mut body = rest.to_vec();
+
+    if let Some(key) = options.session_key {
+        // A configured session key means this connection always encrypts -- encode_frame never
+        // leaves FLAG_ENCRYPTED unset when it has a key to encrypt with. If the header claims
+        // otherwise, either a peer bug or (more likely) a network attacker cleared the bit to
+        // skip decryption and MAC verification entirely; treat that exactly like a failed MAC
+        // rather than silently handing back the raw ciphertext+tag as "plaintext"
+        if flags & FLAG_ENCRYPTED == 0 {
+            return Err(FramingError::AuthenticationFailed);
+        }
+        body = decrypt(key, flags, &body)?;
+    } else if flags & FLAG_ENCRYPTED != 0 {
+        return Err(FramingError::MissingSessionKey);
+    }
+
+    if flags & FLAG_COMPRESSED != 0 {
+        body = inflate(&body, options.max_inflated_size)?;
+    }
+
+    Ok(body)
+}
+
+fn deflate(payload: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(payload)
+        .expect("writing to an in-memory buffer cannot fail");
+    encoder
+        .finish()
+        .expect("flushing an in-memory buffer cannot fail")
+}
+
+/// Inflate `payload`, reading no more than `max_len + 1` decompressed bytes so a small malicious
+/// frame can't be used as a zlib decompression bomb -- we bail the moment the cap is exceeded
+/// rather than ever materializing the full (potentially enormous) decompressed output
+fn inflate(payload: &[u8], max_len: usize) -> Result<Vec<u8>, FramingError> {
+    let mut decoder = ZlibDecoder::new(payload).take(max_len as u64 + 1);
+    let mut result = Vec::new();
+    decoder.read_to_end(&mut result)?;
+    if result.len() > max_len {
+        return Err(FramingError::DecompressedTooLarge {
+            max_inflated_size: max_len,
+        });
+    }
+    Ok(result)
+}
+
+/// Encrypt-then-MAC: AES-128 CFB8 encrypts `payload` under a fresh random IV, then an
+/// HMAC-SHA256 tag is computed over `header` (the frame's flag byte, passed in as associated
+/// data so tampering with the flags is detected too), the IV, and the ciphertext, and appended,
+/// so tampering anywhere in the frame is detectable before anything is decrypted
+fn encrypt(key: &SessionKey, header: u8, payload: &[u8]) -> Vec<u8> {
+    let iv: [u8; 16] = rand::random();
+    let mut ciphertext = payload.to_vec();
+    Aes128Cfb8Enc::new(key.into(), &iv.into()).encrypt(&mut ciphertext);
+
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts a key of any length");
+    mac.update(&[header]);
+    mac.update(&iv);
+    mac.update(&ciphertext);
+    let tag = mac.finalize().into_bytes();
+
+    let mut result = Vec::with_capacity(iv.len() + ciphertext.len() + tag.len());
+    result.extend(iv);
+    result.extend(ciphertext);
+    result.extend(tag);
+    result
+}
+
+fn decrypt(key: &SessionKey, header: u8, payload: &[u8]) -> Result<Vec<u8>, FramingError> {
+    if payload.len() < 16 + MAC_LEN {
+        return Err(FramingError::Truncated);
+    }
+    let (iv_and_ciphertext, tag) = payload.split_at(payload.len() - MAC_LEN);
+    let (iv, ciphertext) = iv_and_ciphertext.split_at(16);
+
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts a key of any length");
+    mac.update(&[header]);
+    mac.update(iv);
+    mac.update(ciphertext);
+    mac.verify_slice(tag)
+        .map_err(|_| FramingError::AuthenticationFailed)?;
+
+    let mut plaintext = ciphertext.to_vec();
+    Aes128Cfb8Dec::new(key.into(), iv.into()).decrypt(&mut plaintext);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_payload(len: usize) -> Vec<u8> {
+        (0..len).map(|_| rand::random()).collect()
+    }
+
+    #[test]
+    fn test_frame_round_trip_uncompressed_unencrypted() {
+        let payload = random_payload(32);
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: None,
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let framed = encode_frame(payload.clone(), options);
+        let back = decode_frame(framed, options).expect("decode should succeed");
+        assert_eq!(payload, back);
+    }
+
+    #[test]
+    fn test_frame_round_trip_compressed() {
+        let payload = vec![7u8; 4096];
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: None,
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let framed = encode_frame(payload.clone(), options);
+        assert!(framed.len() < payload.len(), "repetitive payload should shrink");
+        let back = decode_frame(framed, options).expect("decode should succeed");
+        assert_eq!(payload, back);
+    }
+
+    #[test]
+    fn test_frame_round_trip_encrypted() {
+        let key: SessionKey = rand::random();
+        let payload = random_payload(64);
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: Some(&key),
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let framed = encode_frame(payload.clone(), options);
+        let back = decode_frame(framed, options).expect("decode should succeed");
+        assert_eq!(payload, back);
+    }
+
+    #[test]
+    fn test_frame_round_trip_compressed_and_encrypted() {
+        let key: SessionKey = rand::random();
+        let payload = vec![9u8; 4096];
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: Some(&key),
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let framed = encode_frame(payload.clone(), options);
+        let back = decode_frame(framed, options).expect("decode should succeed");
+        assert_eq!(payload, back);
+    }
+
+    #[test]
+    fn test_decode_encrypted_frame_without_key_errors() {
+        let key: SessionKey = rand::random();
+        let payload = random_payload(32);
+        let encode_options = FrameOptions {
+            compression_threshold: 256,
+            session_key: Some(&key),
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let framed = encode_frame(payload, encode_options);
+        let result = decode_frame(framed, FrameOptions::default());
+        assert!(matches!(result, Err(FramingError::MissingSessionKey)));
+    }
+
+    #[test]
+    fn test_decode_rejects_decompression_bomb() {
+        let payload = vec![0u8; 1_000_000];
+        let encode_options = FrameOptions {
+            compression_threshold: 0,
+            session_key: None,
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+        let framed = encode_frame(payload, encode_options);
+
+        let decode_options = FrameOptions {
+            compression_threshold: 256,
+            session_key: None,
+            max_inflated_size: 1024,
+        };
+        let result = decode_frame(framed, decode_options);
+        assert!(matches!(
+            result,
+            Err(FramingError::DecompressedTooLarge { max_inflated_size: 1024 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_tampered_ciphertext() {
+        let key: SessionKey = rand::random();
+        let payload = random_payload(64);
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: Some(&key),
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let mut framed = encode_frame(payload, options);
+        let last = framed.len() - 1;
+        framed[last] ^= 0xff;
+
+        let result = decode_frame(framed, options);
+        assert!(matches!(result, Err(FramingError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn test_decode_rejects_tampered_header() {
+        let key: SessionKey = rand::random();
+        let payload = random_payload(64);
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: Some(&key),
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let mut framed = encode_frame(payload, options);
+        // Clearing FLAG_ENCRYPTED is the cheapest possible tamper: if decode_frame trusted the
+        // flag instead of requiring it whenever a session key is configured, it would skip
+        // decryption and MAC verification entirely and hand back the raw ciphertext+tag as if it
+        // were valid plaintext
+        framed[0] &= !FLAG_ENCRYPTED;
+
+        let result = decode_frame(framed, options);
+        assert!(matches!(result, Err(FramingError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_encrypted_frame() {
+        let key: SessionKey = rand::random();
+        let options = FrameOptions {
+            compression_threshold: 256,
+            session_key: Some(&key),
+            max_inflated_size: FrameOptions::default().max_inflated_size,
+        };
+
+        let framed = encode_frame(random_payload(8), options);
+        // Keep the header byte plus one byte less than the IV and MAC tag need
+        let truncated = framed[..16 + MAC_LEN].to_vec();
+        let result = decode_frame(truncated, options);
+        assert!(matches!(result, Err(FramingError::Truncated)));
+    }
+}
@@ -11,6 +11,119 @@
 //! on one host and decode at the wrong size at the other host.
 //!
 //! We additionally provide implementations for [String], [Vec<`char`>], and [Vec<_>] of all numeric values.
+//!
+//! Variable-length composite types are framed with a length-delimited encoding: tuples up to
+//! arity 8, [Vec<String>], [Vec<Vec<u8>>], [`std::collections::HashMap`], and
+//! [`std::collections::BTreeMap`] all write each field or element as a `u32` big-endian length
+//! followed by its `into_bytes()` payload, so boundaries can be recovered on the way back out.
+//! [Option<T>] needs no length: it writes a single tag byte (`0` for `None`, `1` for `Some`)
+//! followed by the inner value's payload.
+//!
+//! Those length prefixes are attacker-controlled on any message that crossed the network, so
+//! [`BytesConvertable::try_from_bytes`] checks each one against a [`DecodeLimit`] budget before
+//! trusting it for an allocation, rather than panicking or exhausting memory the way
+//! [`BytesConvertable::from_bytes`] is allowed to. The same goes for malformed *content* behind a
+//! perfectly well-formed length prefix (non-UTF-8 bytes for a `String`, an out-of-range `char`,
+//! ...): `try_from_bytes` returns a [`DecodeError`] for those too instead of panicking.
+
+/// The default byte budget used by [`try_decode`] and [`DecodeLimit::default_limit`] to bound
+/// how much a single message is allowed to claim via length prefixes, absent a cluster-specific
+/// override
+pub const DEFAULT_DECODE_LIMIT: usize = 16 * 1024 * 1024;
+
+/// Errors which can occur while decoding a [`BytesConvertable`] from an untrusted byte buffer
+/// via [`BytesConvertable::try_from_bytes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    /// A length prefix claimed more bytes than remain in the buffer being decoded
+    #[error("length prefix of {len} bytes exceeds the {available} bytes remaining in the buffer")]
+    LengthExceedsBuffer {
+        /// The length the prefix claimed
+        len: usize,
+        /// The bytes actually remaining in the buffer
+        available: usize,
+    },
+    /// A length prefix claimed more bytes than the configured [`DecodeLimit`] allows
+    #[error("length prefix of {len} bytes exceeds the configured decode limit of {limit} bytes")]
+    LimitExceeded {
+        /// The length the prefix claimed
+        len: usize,
+        /// The remaining decode budget at the time of the read
+        limit: usize,
+    },
+    /// A `String` field's bytes were not valid UTF-8
+    #[error("string field is not valid utf-8 (valid up to byte {valid_up_to})")]
+    InvalidUtf8 {
+        /// How many leading bytes of the field actually were valid UTF-8
+        valid_up_to: usize,
+    },
+    /// A `char` field's raw value is not a valid Unicode scalar value
+    #[error("char field's raw value {value} is not a valid Unicode scalar value")]
+    InvalidChar {
+        /// The raw `u32` value that failed to map to a `char`
+        value: u32,
+    },
+    /// A `Compact` big-integer header claimed more bytes than fit in a `u128`
+    #[error("compact big-integer header claims {byte_len} bytes, which exceeds the 16-byte maximum for a u128")]
+    CompactOverflow {
+        /// The byte length the header claimed
+        byte_len: usize,
+    },
+    /// A fixed-width value (a numeric primitive or `bool`) didn't have enough bytes behind its
+    /// length prefix to fill its encoding, or a `Compact` value had no header byte at all
+    #[error("fixed-width value expected {expected} bytes but only {available} were available")]
+    TruncatedValue {
+        /// The number of bytes the value's encoding requires
+        expected: usize,
+        /// The bytes actually available
+        available: usize,
+    },
+}
+
+/// A remaining-bytes budget carried through a single [`BytesConvertable::try_from_bytes`] call,
+/// inspired by bincode's bounded reader. Every length prefix read while decoding is checked
+/// against the budget (and shrinks it) before it is trusted for an allocation, so a peer lying
+/// about a length cannot make a node allocate far beyond the bytes it actually sent.
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeLimit {
+    remaining: usize,
+}
+
+impl DecodeLimit {
+    /// Construct a new decode budget of `limit` bytes
+    pub fn new(limit: usize) -> Self {
+        Self { remaining: limit }
+    }
+
+    /// Construct a decode budget using the cluster-wide [`DEFAULT_DECODE_LIMIT`]
+    pub fn default_limit() -> Self {
+        Self::new(DEFAULT_DECODE_LIMIT)
+    }
+
+    /// Charge `len` bytes against the remaining budget, failing if `len` exceeds either the
+    /// bytes actually `available` in the buffer or the remaining budget itself
+    fn check(&mut self, len: usize, available: usize) -> Result<(), DecodeError> {
+        if len > available {
+            return Err(DecodeError::LengthExceedsBuffer { len, available });
+        }
+        if len > self.remaining {
+            return Err(DecodeError::LimitExceeded {
+                len,
+                limit: self.remaining,
+            });
+        }
+        self.remaining -= len;
+        Ok(())
+    }
+}
+
+/// Decode `T` from an untrusted, remote-sourced buffer using the cluster-wide default
+/// [`DecodeLimit`]. Cluster message deserialization should call this instead of
+/// [`BytesConvertable::from_bytes`], so a peer that lies about a length prefix yields a
+/// [`DecodeError`] instead of a panic or an unbounded allocation.
+pub fn try_decode<T: BytesConvertable>(bytes: Vec<u8>) -> Result<T, DecodeError> {
+    T::try_from_bytes(bytes, &mut DecodeLimit::default_limit())
+}
 
 /// Trait for use with `ractor_cluster_derive::RactorClusterMessage`
 /// derive macro. It defines argument and reply message types which
@@ -21,27 +134,131 @@ pub trait BytesConvertable {
     fn into_bytes(self) -> Vec<u8>;
     /// Deserialize this type from a vector of bytes. Panics are acceptable
     fn from_bytes(bytes: Vec<u8>) -> Self;
+
+    /// Fallible counterpart to [`BytesConvertable::from_bytes`] for buffers that came from an
+    /// untrusted remote peer. Every length prefix read along the way is checked against `limit`
+    /// before it's trusted for an allocation, returning a [`DecodeError`] instead of panicking
+    /// or over-allocating when a prefix is corrupt or malicious.
+    ///
+    /// The default implementation simply defers to [`BytesConvertable::from_bytes`], so it
+    /// panics wherever `from_bytes` does; it suits types whose `from_bytes` cannot panic (`()`,
+    /// raw `Vec<u8>`, `Vec<bool>`, ...). Fixed-width numerics, `bool`, `char`, and `String`
+    /// override it to report truncated or invalid content, and the length-delimited composite
+    /// implementations (tuples, `Vec<String>`, maps, ...) override it to charge each prefix they
+    /// read against `limit`. `Option<T>` passes `limit` through to its inner value.
+    fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError>
+    where
+        Self: Sized,
+    {
+        let _ = limit;
+        Ok(Self::from_bytes(bytes))
+    }
 }
 
 #[cfg(feature = "blanket_serde")]
-/// Contains a blanket implementation for all types that implement serde::Serialize and serde::Deserialize
+/// Contains a blanket implementation for all types that implement serde::Serialize and serde::Deserialize.
+///
+/// The actual wire format is selected at compile time by exactly one of the `serde_pot`,
+/// `serde_bincode`, `serde_cbor`, or `serde_messagepack` features -- pick whichever fits your
+/// cluster: `serde_pot` for the historical default, `serde_bincode` for the most compact
+/// fixed-layout output, `serde_cbor` for self-describing data that cross-language peers can
+/// decode without the schema, and `serde_messagepack` for schema-tolerant maps.
 mod impls {
     use crate::BytesConvertable;
 
-    impl<T: serde::Serialize + serde::de::DeserializeOwned> BytesConvertable for T {
-        fn from_bytes(bytes: Vec<u8>) -> Self {
-            pot::from_slice(&bytes).unwrap()
+    #[cfg(not(any(
+        feature = "serde_pot",
+        feature = "serde_bincode",
+        feature = "serde_cbor",
+        feature = "serde_messagepack"
+    )))]
+    compile_error!(
+        "`blanket_serde` requires exactly one of the `serde_pot`, `serde_bincode`, `serde_cbor`, or `serde_messagepack` features to be enabled"
+    );
+
+    #[cfg(any(
+        all(feature = "serde_pot", feature = "serde_bincode"),
+        all(feature = "serde_pot", feature = "serde_cbor"),
+        all(feature = "serde_pot", feature = "serde_messagepack"),
+        all(feature = "serde_bincode", feature = "serde_cbor"),
+        all(feature = "serde_bincode", feature = "serde_messagepack"),
+        all(feature = "serde_cbor", feature = "serde_messagepack"),
+    ))]
+    compile_error!(
+        "only one of `serde_pot`, `serde_bincode`, `serde_cbor`, or `serde_messagepack` may be enabled at a time"
+    );
+
+    /// Internal dispatch point for the wire-format backend selected via cargo features.
+    /// Swapping backends only ever requires changing the feature flag -- callers always
+    /// go through [`BytesConvertable`] and never see `Codec` directly.
+    trait Codec: Sized {
+        /// Encode `self` with the active backend
+        fn encode(&self) -> Vec<u8>;
+        /// Decode `Self` with the active backend
+        fn decode(bytes: &[u8]) -> Self;
+    }
+
+    impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec for T {
+        #[cfg(feature = "serde_pot")]
+        fn encode(&self) -> Vec<u8> {
+            pot::to_vec(self).unwrap()
+        }
+        #[cfg(feature = "serde_pot")]
+        fn decode(bytes: &[u8]) -> Self {
+            pot::from_slice(bytes).unwrap()
+        }
+
+        #[cfg(feature = "serde_bincode")]
+        fn encode(&self) -> Vec<u8> {
+            bincode::serialize(self).unwrap()
         }
+        #[cfg(feature = "serde_bincode")]
+        fn decode(bytes: &[u8]) -> Self {
+            bincode::deserialize(bytes).unwrap()
+        }
+
+        #[cfg(feature = "serde_cbor")]
+        fn encode(&self) -> Vec<u8> {
+            serde_cbor::to_vec(self).unwrap()
+        }
+        #[cfg(feature = "serde_cbor")]
+        fn decode(bytes: &[u8]) -> Self {
+            serde_cbor::from_slice(bytes).unwrap()
+        }
+
+        #[cfg(feature = "serde_messagepack")]
+        fn encode(&self) -> Vec<u8> {
+            use serde::Serialize;
+            let mut buf = Vec::new();
+            let mut serializer = rmp_serde::Serializer::new(&mut buf)
+                .with_struct_map()
+                .with_string_variants();
+            self.serialize(&mut serializer).unwrap();
+            buf
+        }
+        #[cfg(feature = "serde_messagepack")]
+        fn decode(bytes: &[u8]) -> Self {
+            rmp_serde::from_slice(bytes).unwrap()
+        }
+    }
+
+    impl<T: serde::Serialize + serde::de::DeserializeOwned> BytesConvertable for T {
         fn into_bytes(self) -> Vec<u8> {
-            vec![]
+            Codec::encode(&self)
+        }
+        fn from_bytes(bytes: Vec<u8>) -> Self {
+            Codec::decode(&bytes)
         }
     }
 }
 
+#[cfg(not(feature = "blanket_serde"))]
+pub use impls::Compact;
+
 #[cfg(not(feature = "blanket_serde"))]
 /// Contains the default implementations for the `BytesConvertable` trait
 mod impls {
-    use crate::BytesConvertable;
+    use crate::{BytesConvertable, DecodeError, DecodeLimit};
 
     // ==================== Primitive implementations ==================== //
 
@@ -56,6 +273,15 @@ mod impls {
                     data.copy_from_slice(&bytes[..std::mem::size_of::<Self>()]);
                     Self::from_be_bytes(data)
                 }
+                fn try_from_bytes(bytes: Vec<u8>, _limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+                    let expected = std::mem::size_of::<Self>();
+                    if bytes.len() < expected {
+                        return Err(DecodeError::TruncatedValue { expected, available: bytes.len() });
+                    }
+                    let mut data = [0u8; std::mem::size_of::<Self>()];
+                    data.copy_from_slice(&bytes[..expected]);
+                    Ok(Self::from_be_bytes(data))
+                }
             }
         };
     }
@@ -93,6 +319,12 @@ mod impls {
         fn from_bytes(bytes: Vec<u8>) -> Self {
             bytes[0] == 1u8
         }
+        fn try_from_bytes(bytes: Vec<u8>, _limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            bytes.first().map(|b| *b == 1u8).ok_or(DecodeError::TruncatedValue {
+                expected: 1,
+                available: 0,
+            })
+        }
     }
 
     impl BytesConvertable for char {
@@ -104,6 +336,10 @@ mod impls {
             let u = u32::from_bytes(bytes);
             Self::from_u32(u).unwrap()
         }
+        fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            let value = u32::try_from_bytes(bytes, limit)?;
+            Self::from_u32(value).ok_or(DecodeError::InvalidChar { value })
+        }
     }
 
     impl BytesConvertable for String {
@@ -113,6 +349,11 @@ mod impls {
         fn from_bytes(bytes: Vec<u8>) -> Self {
             String::from_utf8(bytes).unwrap()
         }
+        fn try_from_bytes(bytes: Vec<u8>, _limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            String::from_utf8(bytes).map_err(|err| DecodeError::InvalidUtf8 {
+                valid_up_to: err.utf8_error().valid_up_to(),
+            })
+        }
     }
 
     // ==================== Vectorized implementations ==================== //
@@ -199,12 +440,403 @@ mod impls {
                 .collect::<Vec<_>>()
         }
     }
+
+    // ==================== Compact numeric encoding ==================== //
+
+    /// A SCALE-style compact encoding for unsigned integers, opt-in via this wrapper type.
+    ///
+    /// Unlike the fixed-width numeric implementations above (which always spend
+    /// `size_of::<T>()` bytes, e.g. 8 bytes for a `u64` of `3`), `Compact` spends only as
+    /// many bytes as the value actually needs. The low two bits of the first byte select
+    /// the mode:
+    ///
+    /// * `0b00` -- single byte, remaining six bits hold the value (values < 2^6)
+    /// * `0b01` -- two bytes, little-endian, upper 14 bits hold the value (values < 2^14)
+    /// * `0b10` -- four bytes, little-endian, upper 30 bits hold the value (values < 2^30)
+    /// * `0b11` -- "big-integer" mode: the upper six bits of the first byte encode
+    ///   `byte_len - 4`, followed by that many little-endian bytes
+    ///
+    /// This is a good fit for small counts and ids in cluster RPC messages, where most
+    /// values never approach the fixed width they'd otherwise be encoded at.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Compact(pub u128);
+
+    impl BytesConvertable for Compact {
+        fn into_bytes(self) -> Vec<u8> {
+            let value = self.0;
+            if value < (1 << 6) {
+                vec![(value as u8) << 2]
+            } else if value < (1 << 14) {
+                (((value as u16) << 2) | 0b01).to_le_bytes().to_vec()
+            } else if value < (1 << 30) {
+                (((value as u32) << 2) | 0b10).to_le_bytes().to_vec()
+            } else {
+                let mut le_bytes = value.to_le_bytes().to_vec();
+                while le_bytes.len() > 4 && *le_bytes.last().unwrap() == 0 {
+                    le_bytes.pop();
+                }
+                let header = (((le_bytes.len() - 4) as u8) << 2) | 0b11;
+                let mut result = vec![header];
+                result.extend(le_bytes);
+                result
+            }
+        }
+
+        fn from_bytes(bytes: Vec<u8>) -> Self {
+            let first = bytes[0];
+            match first & 0b11 {
+                0b00 => Compact((first >> 2) as u128),
+                0b01 => {
+                    let mut data = [0u8; 2];
+                    data.copy_from_slice(&bytes[..2]);
+                    Compact((u16::from_le_bytes(data) >> 2) as u128)
+                }
+                0b10 => {
+                    let mut data = [0u8; 4];
+                    data.copy_from_slice(&bytes[..4]);
+                    Compact((u32::from_le_bytes(data) >> 2) as u128)
+                }
+                _ => {
+                    let byte_len = (first >> 2) as usize + 4;
+                    assert!(
+                        byte_len <= 16,
+                        "Compact big-integer header claims {byte_len} bytes, which exceeds the 16-byte maximum for a u128"
+                    );
+                    let mut data = [0u8; 16];
+                    data[..byte_len].copy_from_slice(&bytes[1..1 + byte_len]);
+                    Compact(u128::from_le_bytes(data))
+                }
+            }
+        }
+
+        fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            let Some(&first) = bytes.first() else {
+                return Err(DecodeError::TruncatedValue {
+                    expected: 1,
+                    available: 0,
+                });
+            };
+
+            match first & 0b11 {
+                0b00 => {
+                    limit.check(1, bytes.len())?;
+                    Ok(Compact((first >> 2) as u128))
+                }
+                0b01 => {
+                    limit.check(2, bytes.len())?;
+                    let mut data = [0u8; 2];
+                    data.copy_from_slice(&bytes[..2]);
+                    Ok(Compact((u16::from_le_bytes(data) >> 2) as u128))
+                }
+                0b10 => {
+                    limit.check(4, bytes.len())?;
+                    let mut data = [0u8; 4];
+                    data.copy_from_slice(&bytes[..4]);
+                    Ok(Compact((u32::from_le_bytes(data) >> 2) as u128))
+                }
+                _ => {
+                    let byte_len = (first >> 2) as usize + 4;
+                    if byte_len > 16 {
+                        return Err(DecodeError::CompactOverflow { byte_len });
+                    }
+                    limit.check(1 + byte_len, bytes.len())?;
+                    let mut data = [0u8; 16];
+                    data[..byte_len].copy_from_slice(&bytes[1..1 + byte_len]);
+                    Ok(Compact(u128::from_le_bytes(data)))
+                }
+            }
+        }
+    }
+
+    // ==================== Length-delimited composite implementations ==================== //
+
+    /// Write `payload` to `buf` as a `u32` big-endian length prefix followed by the payload
+    /// itself, so the boundary can be recovered by [`read_length_delimited`]
+    fn write_length_delimited(buf: &mut Vec<u8>, payload: Vec<u8>) {
+        buf.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        buf.extend(payload);
+    }
+
+    /// Read one length-prefixed payload out of `bytes` starting at `*cursor`, advancing
+    /// `*cursor` past it
+    fn read_length_delimited(bytes: &[u8], cursor: &mut usize) -> Vec<u8> {
+        let mut len_bytes = [0u8; 4];
+        len_bytes.copy_from_slice(&bytes[*cursor..*cursor + 4]);
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        *cursor += 4;
+        let payload = bytes[*cursor..*cursor + len].to_vec();
+        *cursor += len;
+        payload
+    }
+
+    /// Fallible, [`DecodeLimit`]-checked counterpart to [`read_length_delimited`]. Charges both
+    /// the 4-byte length prefix itself and the payload it describes against `limit` before
+    /// trusting either for an allocation
+    fn read_length_delimited_checked(
+        bytes: &[u8],
+        cursor: &mut usize,
+        limit: &mut DecodeLimit,
+    ) -> Result<Vec<u8>, DecodeError> {
+        limit.check(4, bytes.len().saturating_sub(*cursor))?;
+        let mut len_bytes = [0u8; 4];
+        len_bytes.copy_from_slice(&bytes[*cursor..*cursor + 4]);
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        *cursor += 4;
+
+        limit.check(len, bytes.len().saturating_sub(*cursor))?;
+        let payload = bytes[*cursor..*cursor + len].to_vec();
+        *cursor += len;
+        Ok(payload)
+    }
+
+    impl<T: BytesConvertable> BytesConvertable for Option<T> {
+        fn into_bytes(self) -> Vec<u8> {
+            match self {
+                None => vec![0u8],
+                Some(value) => {
+                    let mut result = vec![1u8];
+                    result.extend(value.into_bytes());
+                    result
+                }
+            }
+        }
+        fn from_bytes(mut bytes: Vec<u8>) -> Self {
+            if bytes.is_empty() || bytes[0] == 0u8 {
+                None
+            } else {
+                Some(T::from_bytes(bytes.split_off(1)))
+            }
+        }
+        fn try_from_bytes(mut bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            if bytes.is_empty() || bytes[0] == 0u8 {
+                Ok(None)
+            } else {
+                Ok(Some(T::try_from_bytes(bytes.split_off(1), limit)?))
+            }
+        }
+    }
+
+    macro_rules! implement_tuple {
+        {$($name:ident),+} => {
+            impl<$($name: BytesConvertable),+> BytesConvertable for ($($name,)+) {
+                #[allow(non_snake_case)]
+                fn into_bytes(self) -> Vec<u8> {
+                    let ($($name,)+) = self;
+                    let mut result = Vec::new();
+                    $(write_length_delimited(&mut result, $name.into_bytes());)+
+                    result
+                }
+                #[allow(non_snake_case)]
+                fn from_bytes(bytes: Vec<u8>) -> Self {
+                    let mut cursor = 0usize;
+                    $(let $name = <$name>::from_bytes(read_length_delimited(&bytes, &mut cursor));)+
+                    ($($name,)+)
+                }
+                #[allow(non_snake_case)]
+                fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+                    let mut cursor = 0usize;
+                    $(let $name = <$name>::try_from_bytes(read_length_delimited_checked(&bytes, &mut cursor, limit)?, limit)?;)+
+                    Ok(($($name,)+))
+                }
+            }
+        };
+    }
+
+    implement_tuple! {A}
+    implement_tuple! {A, B}
+    implement_tuple! {A, B, C}
+    implement_tuple! {A, B, C, D}
+    implement_tuple! {A, B, C, D, E}
+    implement_tuple! {A, B, C, D, E, F}
+    implement_tuple! {A, B, C, D, E, F, G}
+    implement_tuple! {A, B, C, D, E, F, G, H}
+
+    /// Fallible, [`DecodeLimit`]-checked read of the `u32` big-endian element count prefixing
+    /// every composite collection. Charged against `limit` just like any other length prefix, so
+    /// a bogus huge count cannot be used to justify an oversized allocation: the loop that
+    /// follows will simply fail on its first element once the buffer or budget runs out.
+    fn read_count_checked(
+        bytes: &[u8],
+        cursor: &mut usize,
+        limit: &mut DecodeLimit,
+    ) -> Result<usize, DecodeError> {
+        limit.check(4, bytes.len().saturating_sub(*cursor))?;
+        let mut len_bytes = [0u8; 4];
+        len_bytes.copy_from_slice(&bytes[*cursor..*cursor + 4]);
+        *cursor += 4;
+        Ok(u32::from_be_bytes(len_bytes) as usize)
+    }
+
+    impl BytesConvertable for Vec<String> {
+        fn into_bytes(self) -> Vec<u8> {
+            let mut result = (self.len() as u32).to_be_bytes().to_vec();
+            for item in self.into_iter() {
+                write_length_delimited(&mut result, item.into_bytes());
+            }
+            result
+        }
+        fn from_bytes(bytes: Vec<u8>) -> Self {
+            let mut cursor = 0usize;
+            let mut len_bytes = [0u8; 4];
+            len_bytes.copy_from_slice(&bytes[cursor..cursor + 4]);
+            let count = u32::from_be_bytes(len_bytes) as usize;
+            cursor += 4;
+
+            // `count` is an attacker-controlled u32 prefix with no relation to `bytes.len()`; cap
+            // the eagerly-reserved capacity so a tiny malformed buffer can't claim a multi-gigabyte
+            // allocation before the first (and only possible) element is even read
+            let mut result = Vec::with_capacity(count.min(bytes.len()));
+            for _ in 0..count {
+                result.push(String::from_bytes(read_length_delimited(&bytes, &mut cursor)));
+            }
+            result
+        }
+        fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            let mut cursor = 0usize;
+            let count = read_count_checked(&bytes, &mut cursor, limit)?;
+
+            let mut result = Vec::new();
+            for _ in 0..count {
+                let payload = read_length_delimited_checked(&bytes, &mut cursor, limit)?;
+                result.push(String::try_from_bytes(payload, limit)?);
+            }
+            Ok(result)
+        }
+    }
+
+    impl BytesConvertable for Vec<Vec<u8>> {
+        fn into_bytes(self) -> Vec<u8> {
+            let mut result = (self.len() as u32).to_be_bytes().to_vec();
+            for item in self.into_iter() {
+                write_length_delimited(&mut result, item.into_bytes());
+            }
+            result
+        }
+        fn from_bytes(bytes: Vec<u8>) -> Self {
+            let mut cursor = 0usize;
+            let mut len_bytes = [0u8; 4];
+            len_bytes.copy_from_slice(&bytes[cursor..cursor + 4]);
+            let count = u32::from_be_bytes(len_bytes) as usize;
+            cursor += 4;
+
+            // See the matching comment in `Vec<String>::from_bytes`: cap the eager allocation
+            // against the buffer actually available, not the attacker-controlled count prefix
+            let mut result = Vec::with_capacity(count.min(bytes.len()));
+            for _ in 0..count {
+                result.push(<Vec<u8>>::from_bytes(read_length_delimited(&bytes, &mut cursor)));
+            }
+            result
+        }
+        fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            let mut cursor = 0usize;
+            let count = read_count_checked(&bytes, &mut cursor, limit)?;
+
+            let mut result = Vec::new();
+            for _ in 0..count {
+                let payload = read_length_delimited_checked(&bytes, &mut cursor, limit)?;
+                result.push(<Vec<u8>>::try_from_bytes(payload, limit)?);
+            }
+            Ok(result)
+        }
+    }
+
+    impl<K, V> BytesConvertable for std::collections::HashMap<K, V>
+    where
+        K: BytesConvertable + Eq + std::hash::Hash,
+        V: BytesConvertable,
+    {
+        fn into_bytes(self) -> Vec<u8> {
+            let mut result = (self.len() as u32).to_be_bytes().to_vec();
+            for (key, value) in self.into_iter() {
+                write_length_delimited(&mut result, key.into_bytes());
+                write_length_delimited(&mut result, value.into_bytes());
+            }
+            result
+        }
+        fn from_bytes(bytes: Vec<u8>) -> Self {
+            let mut cursor = 0usize;
+            let mut len_bytes = [0u8; 4];
+            len_bytes.copy_from_slice(&bytes[cursor..cursor + 4]);
+            let count = u32::from_be_bytes(len_bytes) as usize;
+            cursor += 4;
+
+            // See the matching comment in `Vec<String>::from_bytes`: cap the eager allocation
+            // against the buffer actually available, not the attacker-controlled count prefix
+            let mut result = Self::with_capacity(count.min(bytes.len()));
+            for _ in 0..count {
+                let key = K::from_bytes(read_length_delimited(&bytes, &mut cursor));
+                let value = V::from_bytes(read_length_delimited(&bytes, &mut cursor));
+                result.insert(key, value);
+            }
+            result
+        }
+        fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            let mut cursor = 0usize;
+            let count = read_count_checked(&bytes, &mut cursor, limit)?;
+
+            let mut result = Self::new();
+            for _ in 0..count {
+                let key_bytes = read_length_delimited_checked(&bytes, &mut cursor, limit)?;
+                let key = K::try_from_bytes(key_bytes, limit)?;
+                let value_bytes = read_length_delimited_checked(&bytes, &mut cursor, limit)?;
+                let value = V::try_from_bytes(value_bytes, limit)?;
+                result.insert(key, value);
+            }
+            Ok(result)
+        }
+    }
+
+    impl<K, V> BytesConvertable for std::collections::BTreeMap<K, V>
+    where
+        K: BytesConvertable + Ord,
+        V: BytesConvertable,
+    {
+        fn into_bytes(self) -> Vec<u8> {
+            let mut result = (self.len() as u32).to_be_bytes().to_vec();
+            for (key, value) in self.into_iter() {
+                write_length_delimited(&mut result, key.into_bytes());
+                write_length_delimited(&mut result, value.into_bytes());
+            }
+            result
+        }
+        fn from_bytes(bytes: Vec<u8>) -> Self {
+            let mut cursor = 0usize;
+            let mut len_bytes = [0u8; 4];
+            len_bytes.copy_from_slice(&bytes[cursor..cursor + 4]);
+            let count = u32::from_be_bytes(len_bytes) as usize;
+            cursor += 4;
+
+            let mut result = Self::new();
+            for _ in 0..count {
+                let key = K::from_bytes(read_length_delimited(&bytes, &mut cursor));
+                let value = V::from_bytes(read_length_delimited(&bytes, &mut cursor));
+                result.insert(key, value);
+            }
+            result
+        }
+        fn try_from_bytes(bytes: Vec<u8>, limit: &mut DecodeLimit) -> Result<Self, DecodeError> {
+            let mut cursor = 0usize;
+            let count = read_count_checked(&bytes, &mut cursor, limit)?;
+
+            let mut result = Self::new();
+            for _ in 0..count {
+                let key_bytes = read_length_delimited_checked(&bytes, &mut cursor, limit)?;
+                let key = K::try_from_bytes(key_bytes, limit)?;
+                let value_bytes = read_length_delimited_checked(&bytes, &mut cursor, limit)?;
+                let value = V::try_from_bytes(value_bytes, limit)?;
+                result.insert(key, value);
+            }
+            Ok(result)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::BytesConvertable;
-    use crate::{message::BoxedDowncastErr, Message};
+    use crate::{message::BoxedDowncastErr, DecodeError, Message};
+    #[cfg(not(feature = "blanket_serde"))]
+    use crate::Compact;
     use rand::{distributions::Alphanumeric, thread_rng, Rng};
 
     fn random_string() -> String {
@@ -294,6 +926,166 @@ mod tests {
     run_vector_type_test! {char}
     run_vector_type_test! {bool}
 
+    #[test]
+    #[cfg(not(feature = "blanket_serde"))]
+    fn test_compact_round_trip() {
+        let cases = [
+            0u128,
+            1,
+            63,
+            64,
+            (1 << 14) - 1,
+            1 << 14,
+            (1 << 30) - 1,
+            1 << 30,
+            u64::MAX as u128,
+            u128::MAX,
+        ];
+
+        for value in cases {
+            let bytes = Compact(value).into_bytes();
+            let back = Compact::from_bytes(bytes);
+            assert_eq!(Compact(value), back);
+        }
+    }
+
+    #[test]
+    fn test_option_round_trip() {
+        let some_data: Option<u32> = Some(1234);
+        let bytes = some_data.into_bytes();
+        assert_eq!(Some(1234), <Option<u32>>::from_bytes(bytes));
+
+        let none_data: Option<u32> = None;
+        let bytes = none_data.into_bytes();
+        assert_eq!(None, <Option<u32>>::from_bytes(bytes));
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        let test_data = (42u32, random_string(), vec![1u8, 2, 3]);
+        let bytes = test_data.clone().into_bytes();
+        let back = <(u32, String, Vec<u8>)>::from_bytes(bytes);
+        assert_eq!(test_data, back);
+    }
+
+    #[test]
+    fn test_vec_string_round_trip() {
+        let test_data = vec![random_string(), random_string(), random_string()];
+        let bytes = test_data.clone().into_bytes();
+        let back = <Vec<String>>::from_bytes(bytes);
+        assert_eq!(test_data, back);
+    }
+
+    #[test]
+    fn test_vec_vec_u8_round_trip() {
+        let test_data = vec![vec![1u8, 2, 3], vec![], vec![4u8, 5]];
+        let bytes = test_data.clone().into_bytes();
+        let back = <Vec<Vec<u8>>>::from_bytes(bytes);
+        assert_eq!(test_data, back);
+    }
+
+    #[test]
+    fn test_hash_map_round_trip() {
+        use std::collections::HashMap;
+
+        let mut test_data = HashMap::new();
+        test_data.insert(random_string(), 1u32);
+        test_data.insert(random_string(), 2u32);
+
+        let bytes = test_data.clone().into_bytes();
+        let back = <HashMap<String, u32>>::from_bytes(bytes);
+        assert_eq!(test_data, back);
+    }
+
+    #[test]
+    fn test_btree_map_round_trip() {
+        use std::collections::BTreeMap;
+
+        let mut test_data = BTreeMap::new();
+        test_data.insert(random_string(), 1u32);
+        test_data.insert(random_string(), 2u32);
+
+        let bytes = test_data.clone().into_bytes();
+        let back = <BTreeMap<String, u32>>::from_bytes(bytes);
+        assert_eq!(test_data, back);
+    }
+
+    #[test]
+    fn test_try_from_bytes_round_trip() {
+        use crate::{try_decode, DecodeLimit};
+
+        let test_data = vec![random_string(), random_string()];
+        let bytes = test_data.clone().into_bytes();
+
+        let back = <Vec<String>>::try_from_bytes(bytes.clone(), &mut DecodeLimit::default_limit())
+            .expect("decode should succeed within the default limit");
+        assert_eq!(test_data, back);
+
+        let back: Vec<String> = try_decode(bytes).expect("decode should succeed within the default limit");
+        assert_eq!(test_data, back);
+    }
+
+    #[test]
+    fn test_try_from_bytes_rejects_truncated_length_prefix() {
+        use crate::DecodeLimit;
+
+        // Claims a 1000-byte element but the buffer has nothing else in it
+        let mut bytes = 1u32.to_be_bytes().to_vec();
+        bytes.extend(1000u32.to_be_bytes());
+
+        let result = <Vec<String>>::try_from_bytes(bytes, &mut DecodeLimit::default_limit());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_try_from_bytes_rejects_limit_exceeded() {
+        use crate::DecodeLimit;
+
+        let test_data = vec!["a".repeat(100)];
+        let bytes = test_data.into_bytes();
+
+        let result = <Vec<String>>::try_from_bytes(bytes, &mut DecodeLimit::new(8));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_try_from_bytes_rejects_invalid_utf8_instead_of_panicking() {
+        use crate::DecodeLimit;
+
+        let invalid_utf8 = vec![0xffu8, 0xfe, 0xfd];
+        let result = String::try_from_bytes(invalid_utf8, &mut DecodeLimit::default_limit());
+        assert!(matches!(result, Err(DecodeError::InvalidUtf8 { .. })));
+
+        // The same hole is reachable through a composite that nests a `String`
+        // Element count 1, then one element of length 3
+        let mut framed = vec![0u8, 0, 0, 1, 0, 0, 0, 3];
+        framed.extend([0xffu8, 0xfe, 0xfd]);
+        let result = <Vec<String>>::try_from_bytes(framed, &mut DecodeLimit::default_limit());
+        assert!(matches!(result, Err(DecodeError::InvalidUtf8 { .. })));
+    }
+
+    #[test]
+    fn test_try_from_bytes_rejects_invalid_char_instead_of_panicking() {
+        use crate::DecodeLimit;
+
+        // 0x110000 is one past the maximum valid Unicode scalar value
+        let bytes = 0x0011_0000u32.into_bytes();
+        let result = char::try_from_bytes(bytes, &mut DecodeLimit::default_limit());
+        assert!(matches!(result, Err(DecodeError::InvalidChar { .. })));
+    }
+
+    #[test]
+    #[cfg(not(feature = "blanket_serde"))]
+    fn test_try_from_bytes_rejects_oversized_compact_header_instead_of_panicking() {
+        use crate::DecodeLimit;
+
+        // Header byte 0b1111_1111: mode 0b11 ("big-integer"), with (0b111111 + 4) = 67 claimed
+        // bytes -- more than the 16 a u128 can hold
+        let bytes = vec![0b1111_1111u8];
+        let result = Compact::try_from_bytes(bytes, &mut DecodeLimit::default_limit());
+        assert!(matches!(result, Err(DecodeError::CompactOverflow { .. })));
+    }
+
     #[test]
     fn test_boxed_downcast_error() {
         let err = BoxedDowncastErr;